@@ -1,22 +1,34 @@
-use slog::{debug, info, Logger};
+use std::time::{Duration, Instant};
 
 use k8s_openapi::api::batch::v1::Job;
 use k8s_openapi::api::core::v1::Pod;
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use k8s_openapi::api::policy::v1beta1::Eviction;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{
+    DeleteOptions, ObjectMeta, OwnerReference, Status,
+};
 use kube::{
     api::{Api, DeleteParams, Meta},
     Client,
 };
+use tokio::time::delay_for;
+use tracing::{debug, error, info, warn};
 
 type Result = std::result::Result<(), crate::errors::kubernetes::Error>;
 
+const EVICTION_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const EVICTION_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
 enum KnownResource {
     Pod(Box<Pod>),
     Job(Box<Job>),
 }
 
-pub async fn delete_pod(logger: &Logger, pod: &Pod) -> Result {
+pub async fn delete_pod(
+    pod: &Pod,
+    disable_eviction: bool,
+    eviction_grace_period_seconds: Option<i64>,
+    eviction_timeout: Duration,
+) -> Result {
     let meta = &pod.meta();
     let namespace = match &meta.namespace {
         Some(ns) => ns.clone(),
@@ -27,9 +39,7 @@ pub async fn delete_pod(logger: &Logger, pod: &Pod) -> Result {
     let mut delete_order: Vec<KnownResource> = Vec::new();
     delete_order.push(KnownResource::Pod(Box::new(pod.clone())));
 
-    while !owner_refs.is_empty() {
-        let owner = owner_refs.pop().unwrap();
-
+    while let Some(owner) = owner_refs.pop() {
         let client = Client::try_default().await?;
         match owner.kind.as_str() {
             "Job" => {
@@ -42,10 +52,8 @@ pub async fn delete_pod(logger: &Logger, pod: &Pod) -> Result {
             }
             _ => {
                 debug!(
-                    logger,
                     "Unknown resource type: {}/{}. Unable to delete it!",
-                    owner.api_version,
-                    owner.kind
+                    owner.api_version, owner.kind
                 );
                 break;
             }
@@ -56,18 +64,105 @@ pub async fn delete_pod(logger: &Logger, pod: &Pod) -> Result {
 
     for target in delete_order {
         match target {
+            // Only the leaf pod is a voluntary-disruption candidate; the
+            // owning workload resources have no PodDisruptionBudget of
+            // their own, so they're always hard-deleted.
             KnownResource::Pod(target) => {
-                delete_resource(logger, target).await?;
+                if disable_eviction {
+                    delete_resource(target).await?;
+                } else {
+                    evict_pod(
+                        &target,
+                        &namespace,
+                        eviction_grace_period_seconds,
+                        eviction_timeout,
+                    )
+                    .await?;
+                }
             }
             KnownResource::Job(target) => {
-                delete_resource(logger, target).await?;
+                delete_resource(target).await?;
             }
         }
     }
     Ok(())
 }
 
-async fn delete_resource<T>(logger: &Logger, target: Box<T>) -> Result
+async fn evict_pod(
+    pod: &Pod,
+    namespace: &str,
+    grace_period_seconds: Option<i64>,
+    eviction_timeout: Duration,
+) -> Result {
+    let name = match &pod.metadata.name {
+        Some(name) => name.clone(),
+        None => return Ok(()),
+    };
+
+    let eviction = Eviction {
+        metadata: ObjectMeta {
+            name: Some(name.clone()),
+            namespace: Some(namespace.to_string()),
+            ..Default::default()
+        },
+        delete_options: grace_period_seconds.map(|seconds| DeleteOptions {
+            grace_period_seconds: Some(seconds),
+            ..Default::default()
+        }),
+    };
+
+    let body = serde_json::to_vec(&eviction).expect("Eviction always serializes to JSON");
+
+    let deadline = Instant::now() + eviction_timeout;
+    let mut backoff = EVICTION_INITIAL_BACKOFF;
+
+    loop {
+        let client = Client::try_default().await?;
+        let request = http::Request::post(format!(
+            "/api/v1/namespaces/{}/pods/{}/eviction",
+            namespace, name
+        ))
+        .header("Content-Type", "application/json")
+        .body(body.clone())
+        .expect("eviction request is well formed");
+
+        match client.request::<Status>(request).await {
+            Ok(_) => {
+                info!("Evicted pod {}/{}", namespace, name);
+                return Ok(());
+            }
+            // The pod is already gone: evicting it further is a no-op success.
+            Err(kube::Error::Api(response)) if response.code == 404 => {
+                info!(
+                    "Pod {}/{} was already gone, treating eviction as successful",
+                    namespace, name
+                );
+                return Ok(());
+            }
+            // 429 means the eviction would violate a PodDisruptionBudget;
+            // back off and let the budget recover before retrying.
+            Err(kube::Error::Api(response)) if response.code == 429 => {
+                if Instant::now() + backoff > deadline {
+                    error!(
+                        "Eviction of pod {}/{} timed out after {:?} waiting on a PodDisruptionBudget",
+                        namespace, name, eviction_timeout
+                    );
+                    return Err(kube::Error::Api(response).into());
+                }
+
+                warn!(
+                    "Eviction of {}/{} blocked by a PodDisruptionBudget, retrying in {:?}",
+                    namespace, name, backoff
+                );
+                delay_for(backoff).await;
+                backoff = (backoff * 2).min(EVICTION_MAX_BACKOFF);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+async fn delete_resource<T>(target: Box<T>) -> Result
 where
     T: k8s_openapi::Resource + Clone + serde::de::DeserializeOwned + Meta,
 {
@@ -80,9 +175,9 @@ where
     let last_path = resource_name.rfind(':').map(|x| x + 1).unwrap_or(0);
     let resource_name = resource_name[(last_path)..].to_string();
 
-    info!(logger, "Deleting {} {}/{}", resource_name, namespace, &name);
+    info!("Deleting {} {}/{}", resource_name, namespace, &name);
     let api: Api<T> = Api::namespaced(client, namespace);
-    api.delete(&name, &DeleteParams::default()).await?;
+    api.delete(name, &DeleteParams::default()).await?;
     Ok(())
 }
 
@@ -3,37 +3,70 @@ mod istio;
 
 use k8s_openapi::api::core::v1::Pod;
 use k8s_openapi::api::core::v1::PodStatus;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{warn};
 
 use tokio::time::delay_for;
 
+use crate::metrics::Metrics;
+
 #[derive(Debug)]
 pub struct CleanupPod {
-    istio_container_name: String,
-    istio_deadline_ms: u32,
+    disable_eviction: bool,
+    eviction_grace_period_seconds: Option<i64>,
+    eviction_timeout: Duration,
+    metrics: Arc<Metrics>,
 }
 
 impl CleanupPod {
-    pub fn new(istio_container_name: &str, istio_deadline_ms: u32) -> Self {
+    pub fn new(
+        disable_eviction: bool,
+        eviction_grace_period_seconds: Option<i64>,
+        eviction_timeout: Duration,
+        metrics: Arc<Metrics>,
+    ) -> Self {
         Self {
-            istio_container_name: istio_container_name.to_string(),
-            istio_deadline_ms,
+            disable_eviction,
+            eviction_grace_period_seconds,
+            eviction_timeout,
+            metrics,
         }
     }
 
-    pub async fn cleanup_pod(&self, pod: &Pod) -> Result<(), crate::errors::Error> {
-        if let Some(ip) = self.get_istio_container_ip(&pod) {
+    /// `istio_container_name` and `istio_deadline` are resolved per-namespace
+    /// by the caller, since they can be overridden per namespace in config.
+    pub async fn cleanup_pod(
+        &self,
+        pod: &Pod,
+        istio_container_name: &str,
+        istio_deadline: Duration,
+    ) -> Result<(), crate::errors::Error> {
+        if let Some(ip) = Self::get_istio_container_ip(pod, istio_container_name) {
             istio::stop_istio(ip).await?;
-            delay_for(Duration::from_millis(self.istio_deadline_ms.into())).await;
+            self.metrics.istio_shutdowns_total.inc();
+            delay_for(istio_deadline).await;
+        }
+
+        let result = delete::delete_pod(
+            pod,
+            self.disable_eviction,
+            self.eviction_grace_period_seconds,
+            self.eviction_timeout,
+        )
+        .await;
+
+        match &result {
+            Ok(_) => self.metrics.cleanup_successes_total.inc(),
+            Err(_) => self.metrics.cleanup_failures_total.inc(),
         }
 
-        delete::delete_pod(pod).await?;
+        result?;
 
         Ok(())
     }
 
-    fn get_istio_container_ip(&self, pod: &Pod) -> Option<String> {
+    fn get_istio_container_ip(pod: &Pod, istio_container_name: &str) -> Option<String> {
         let status: &PodStatus = match &pod.status {
             None => {
                 warn!("Pod didn't return a status, will not disable Istio");
@@ -47,7 +80,7 @@ impl CleanupPod {
             Some(statuses) => {
                 if statuses
                     .iter()
-                    .any(|status| status.name == self.istio_container_name)
+                    .any(|status| status.name == istio_container_name)
                 {
                     status.pod_ip.clone()
                 } else {
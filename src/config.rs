@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::WatcherArgs;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KillCondition {
+    Any,
+    All,
+}
+
+/// Per-namespace tuning, every field optional so an entry only needs to set
+/// what it wants to override; anything left out falls back to the global
+/// default from `Config`.
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct NamespaceOverride {
+    #[serde(default, with = "humantime_serde::option")]
+    critical_deadline: Option<Duration>,
+    #[serde(default, with = "humantime_serde::option")]
+    istio_deadline: Option<Duration>,
+    #[serde(default)]
+    istio_container_name: Option<String>,
+    #[serde(default)]
+    default_kill_condition: Option<KillCondition>,
+}
+
+/// Layered pod-watcher configuration: defaults, overlaid by `--config`
+/// file contents, overlaid by environment variables, overlaid by flags.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    #[serde(with = "humantime_serde")]
+    pub critical_deadline: Duration,
+    #[serde(with = "humantime_serde")]
+    pub istio_deadline: Duration,
+    pub istio_container_name: String,
+    pub default_kill_condition: KillCondition,
+    pub disable_eviction: bool,
+    pub eviction_grace_period_seconds: Option<i64>,
+    #[serde(with = "humantime_serde")]
+    pub eviction_timeout: Duration,
+    pub metrics_addr: SocketAddr,
+    #[serde(with = "humantime_serde")]
+    pub slow_poll_threshold: Duration,
+    pub max_cleanup_attempts: u32,
+    #[serde(default)]
+    pub namespaces: HashMap<String, NamespaceOverride>,
+}
+
+/// `Config`, resolved for a single namespace: the value pod-watcher should
+/// actually use when evaluating a pod in that namespace.
+#[derive(Debug, Clone)]
+pub struct NamespaceConfig {
+    pub critical_deadline: Duration,
+    pub istio_deadline: Duration,
+    pub istio_container_name: String,
+    pub default_kill_condition: KillCondition,
+}
+
+impl Config {
+    pub fn for_namespace(&self, namespace: &str) -> NamespaceConfig {
+        let over = self.namespaces.get(namespace);
+
+        NamespaceConfig {
+            critical_deadline: over
+                .and_then(|o| o.critical_deadline)
+                .unwrap_or(self.critical_deadline),
+            istio_deadline: over
+                .and_then(|o| o.istio_deadline)
+                .unwrap_or(self.istio_deadline),
+            istio_container_name: over
+                .and_then(|o| o.istio_container_name.clone())
+                .unwrap_or_else(|| self.istio_container_name.clone()),
+            default_kill_condition: over
+                .and_then(|o| o.default_kill_condition.clone())
+                .unwrap_or_else(|| self.default_kill_condition.clone()),
+        }
+    }
+}
+
+/// Builds the layered `Config`: defaults, a YAML/TOML file (`--config`),
+/// environment variables (`PODWATCHER_*`), then flags passed on the CLI,
+/// each layer overriding the one before it.
+pub fn load(opts: &WatcherArgs) -> std::result::Result<Config, crate::errors::config::Error> {
+    let mut builder = ::config::Config::new();
+
+    builder.set_default("critical_deadline", "1s")?;
+    builder.set_default("istio_deadline", "5s")?;
+    builder.set_default("istio_container_name", "istio-proxy")?;
+    builder.set_default("default_kill_condition", "any")?;
+    builder.set_default("disable_eviction", false)?;
+    builder.set_default("eviction_timeout", "30s")?;
+    builder.set_default("metrics_addr", "0.0.0.0:9090")?;
+    builder.set_default("slow_poll_threshold", "5s")?;
+    builder.set_default("max_cleanup_attempts", 5)?;
+
+    if let Some(path) = &opts.config {
+        builder.merge(::config::File::with_name(path))?;
+    }
+
+    // Most top-level `Config` field names already contain an underscore
+    // (`critical_deadline`, `slow_poll_threshold`, ...), so a single "_"
+    // separator would get rewritten into a nested path (e.g.
+    // `PODWATCHER_CRITICAL_DEADLINE` -> `critical.deadline`) that matches
+    // nothing, silently swallowing the override. "__" can't collide with a
+    // snake_case field name.
+    builder.merge(::config::Environment::with_prefix("PODWATCHER").separator("__"))?;
+
+    if let Some(value) = &opts.critical_deadline {
+        builder.set("critical_deadline", value.clone())?;
+    }
+    if let Some(value) = &opts.istio_deadline {
+        builder.set("istio_deadline", value.clone())?;
+    }
+    if let Some(value) = &opts.istio_container_name {
+        builder.set("istio_container_name", value.clone())?;
+    }
+    if opts.disable_eviction {
+        builder.set("disable_eviction", true)?;
+    }
+    if let Some(value) = opts.eviction_grace_period_seconds {
+        builder.set("eviction_grace_period_seconds", value)?;
+    }
+    if let Some(value) = &opts.eviction_timeout {
+        builder.set("eviction_timeout", value.clone())?;
+    }
+    if let Some(value) = opts.metrics_addr {
+        builder.set("metrics_addr", value.to_string())?;
+    }
+    if let Some(value) = &opts.slow_poll_threshold {
+        builder.set("slow_poll_threshold", value.clone())?;
+    }
+    if let Some(value) = opts.max_cleanup_attempts {
+        builder.set("max_cleanup_attempts", value as i64)?;
+    }
+
+    Ok(builder.try_into()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn empty_opts() -> WatcherArgs {
+        WatcherArgs {
+            config: None,
+            istio_container_name: None,
+            istio_deadline: None,
+            critical_deadline: None,
+            disable_eviction: false,
+            eviction_grace_period_seconds: None,
+            eviction_timeout: None,
+            metrics_addr: None,
+            slow_poll_threshold: None,
+            max_cleanup_attempts: None,
+        }
+    }
+
+    // A single test, covering every layering behavior we care about, so the
+    // env vars it sets can't race against another #[test] also mutating the
+    // process environment.
+    #[test]
+    fn env_vars_layer_over_defaults_and_under_flags() {
+        env::set_var("PODWATCHER_CRITICAL_DEADLINE", "42s");
+        env::set_var("PODWATCHER_SLOW_POLL_THRESHOLD", "9s");
+        env::set_var("PODWATCHER_MAX_CLEANUP_ATTEMPTS", "9");
+
+        // Flat field names already contain underscores; the env var
+        // shouldn't get swallowed as an unmatched nested path.
+        let config = load(&empty_opts()).unwrap();
+        assert_eq!(config.critical_deadline, Duration::from_secs(42));
+        assert_eq!(config.slow_poll_threshold, Duration::from_secs(9));
+        assert_eq!(config.max_cleanup_attempts, 9);
+
+        // An explicit flag still wins over the environment variable.
+        let mut opts = empty_opts();
+        opts.critical_deadline = Some("7s".to_string());
+        let config = load(&opts).unwrap();
+        assert_eq!(config.critical_deadline, Duration::from_secs(7));
+
+        env::remove_var("PODWATCHER_CRITICAL_DEADLINE");
+        env::remove_var("PODWATCHER_SLOW_POLL_THRESHOLD");
+        env::remove_var("PODWATCHER_MAX_CLEANUP_ATTEMPTS");
+    }
+}
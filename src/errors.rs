@@ -1,3 +1,7 @@
+// error_chain's expansion references a `has_error_description_deprecated` cfg
+// that predates rustc's `--check-cfg` lint; it's not ours to fix.
+#![allow(unexpected_cfgs)]
+
 use error_chain::error_chain;
 
 error_chain! {
@@ -46,3 +50,21 @@ pub mod reqwest {
         }
     }
 }
+
+pub mod config {
+    use error_chain::error_chain;
+
+    error_chain! {
+        types {
+        }
+
+        foreign_links {
+            Fmt(::std::fmt::Error);
+            Io(::std::io::Error) #[cfg(unix)];
+            Config(::config::ConfigError);
+        }
+
+        errors {
+        }
+    }
+}
@@ -1,19 +1,31 @@
 mod cleanup;
+mod config;
 mod errors;
+mod metrics;
+mod retry;
 
 use chrono::prelude::*;
 use clap::{ArgGroup, Clap};
 use dotenv::dotenv;
 use std::collections::{BTreeMap, BTreeSet};
-use std::time::Duration;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
 
-use tokio::time::delay_for;
+use futures::StreamExt;
 
 use k8s_openapi::api::core::v1::Pod;
-use kube::{api::Api, Client};
+use kube::{
+    api::{Api, ListParams},
+    Client,
+};
+use kube_runtime::watcher::Event;
 
 use tracing::{instrument, Level, debug, error, info, trace, warn};
 
+use config::{Config, KillCondition};
+use metrics::Metrics;
+
 
 #[derive(Clap, Debug)]
 #[clap(group = ArgGroup::new("logging"))]
@@ -56,24 +68,60 @@ struct Opts {
     logging_opts: LoggingOpts,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-enum KillCondition {
-    Any,
-    All,
-}
-
+/// Every field here is an override: it's only applied on top of the
+/// `--config` file and environment variables when actually set, so the
+/// layering in `config::load` decides the real default.
 #[derive(Clap, Debug)]
 pub struct WatcherArgs {
+    /// Path to a YAML/TOML file layered under environment variables and
+    /// flags. See `config::Config` for the supported keys.
+    #[clap(long, env = "PODWATCHER_CONFIG")]
+    config: Option<String>,
+
     /// Name of the Istio Container inside the Pod.
     /// When the container is found, it will be shut down nicely.
-    #[clap(long, default_value = "istio-proxy", env = "ISTIO_CONTAINER_NAME")]
-    istio_container_name: String,
-
-    #[clap(long, default_value = "5000", env = "ISTIO_DEADLINE")]
-    istio_deadline_ms: u32,
-
-    #[clap(long, default_value = "1000", env = "CONTAINER_DEADLINE")]
-    critical_deadline: i64,
+    #[clap(long, env = "ISTIO_CONTAINER_NAME")]
+    istio_container_name: Option<String>,
+
+    /// How long to wait after asking Istio to shut down, e.g. "5s" or "1500ms".
+    #[clap(long, env = "ISTIO_DEADLINE")]
+    istio_deadline: Option<String>,
+
+    /// How long a critical container may stay terminated before pod-watcher
+    /// reaps it, e.g. "1s". Restart-threshold and waiting-reason matches do
+    /// not wait for this deadline.
+    #[clap(long, env = "CONTAINER_DEADLINE")]
+    critical_deadline: Option<String>,
+
+    /// Use a hard delete instead of the Eviction API, bypassing
+    /// PodDisruptionBudget checks entirely.
+    #[clap(long, env = "DISABLE_EVICTION")]
+    disable_eviction: bool,
+
+    /// grace period, in seconds, passed to the Eviction's deleteOptions.
+    /// Defaults to the API server's own default when unset.
+    #[clap(long, env = "EVICTION_GRACE_PERIOD_SECONDS")]
+    eviction_grace_period_seconds: Option<i64>,
+
+    /// overall deadline to keep retrying an eviction that's being blocked
+    /// by a PodDisruptionBudget before giving up, e.g. "30s".
+    #[clap(long, env = "EVICTION_TIMEOUT")]
+    eviction_timeout: Option<String>,
+
+    /// Address the admin HTTP server (/metrics, /healthz) binds to.
+    #[clap(long, env = "METRICS_ADDR")]
+    metrics_addr: Option<SocketAddr>,
+
+    /// Log a warning whenever a single list/relist against the Kubernetes
+    /// API takes longer than this, e.g. "5s". Individual watch events are
+    /// not timed.
+    #[clap(long, env = "SLOW_POLL_THRESHOLD")]
+    slow_poll_threshold: Option<String>,
+
+    /// How many times to retry a failed pod cleanup, with exponential
+    /// backoff, before giving up and dead-lettering it.
+    #[clap(long, env = "MAX_CLEANUP_ATTEMPTS")]
+    max_cleanup_attempts: Option<u32>,
 }
 
 #[tokio::main]
@@ -95,7 +143,18 @@ async fn run() -> i32 {
 
     info!("Starting up....");
 
-    let result = match watch_k8s(&opts.args).await {
+    let config = match config::load(&opts.args) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Unable to build configuration! Error: {:?}", e.to_string());
+            return 1;
+        }
+    };
+
+    let metrics = Arc::new(Metrics::new());
+    tokio::spawn(metrics::serve(config.metrics_addr, metrics.clone()));
+
+    let result = match watch_k8s(&config, &metrics).await {
         Ok(_) => 0,
         Err(e) => {
             error!("Unrecoverable error! Error: {:?}", e.to_string());
@@ -108,85 +167,187 @@ async fn run() -> i32 {
     result
 }
 
-#[instrument(skip(opts))]
-async fn watch_k8s(opts: &WatcherArgs) -> Result<(), errors::kubernetes::Error> {
-    let cleanup = cleanup::CleanupPod::new(&opts.istio_container_name, opts.istio_deadline_ms);
-    let mut pods_being_deleted: BTreeMap<String, i64> = BTreeMap::new();
-
-    loop {
-        check_all_pods(opts.critical_deadline, &mut pods_being_deleted, &cleanup).await?;
+#[instrument(skip(config, metrics))]
+async fn watch_k8s(config: &Config, metrics: &Arc<Metrics>) -> Result<(), errors::kubernetes::Error> {
+    let cleanup = cleanup::CleanupPod::new(
+        config.disable_eviction,
+        config.eviction_grace_period_seconds,
+        config.eviction_timeout,
+        metrics.clone(),
+    );
+    let mut cleanup_tracker = retry::CleanupTracker::new();
+    let mut watched_pods: BTreeMap<String, PodContainer> = BTreeMap::new();
+    let slow_poll_threshold = config.slow_poll_threshold;
 
-        delay_for(Duration::from_secs(5)).await;
+    let client = Client::try_default().await?;
+    let pods: Api<Pod> = Api::all(client);
+    let mut events = kube_runtime::watcher(pods, ListParams::default()).boxed();
 
-        let now = Utc::now().timestamp_millis();
+    loop {
+        let poll_started = Instant::now();
+        let event = match events.next().await {
+            Some(Ok(event)) => event,
+            Some(Err(e)) => {
+                error!("Watch stream errored, will keep retrying: {:?}", e);
+                continue;
+            }
+            None => {
+                warn!("Watch stream ended, restarting it");
+                let client = Client::try_default().await?;
+                let pods: Api<Pod> = Api::all(client);
+                events = kube_runtime::watcher(pods, ListParams::default()).boxed();
+                continue;
+            }
+        };
 
-        let mut keys_to_remove = Vec::new();
+        match event {
+            Event::Applied(pod) => {
+                apply_pod(
+                    pod,
+                    config,
+                    &mut watched_pods,
+                    &mut cleanup_tracker,
+                    &cleanup,
+                    metrics,
+                )
+                .await;
+            }
+            Event::Deleted(pod) => {
+                if let Some(uid) = pod.metadata.uid {
+                    watched_pods.remove(&uid);
+                    cleanup_tracker.forget(&uid);
+                }
+            }
+            Event::Restarted(pods) => {
+                // Only `Restarted` corresponds to an actual list/relist call
+                // (the initial sync, or a resync after the watch falls too
+                // far behind); `Applied`/`Deleted` are delivered as they
+                // trickle in from the long-poll watch, so timing those would
+                // measure how long it's been since the last pod changed, not
+                // API latency.
+                let elapsed = poll_started.elapsed();
+                metrics.list_duration_seconds.observe(elapsed.as_secs_f64());
+                if elapsed > slow_poll_threshold {
+                    warn!(
+                        "Watch list/relist took {:?}, exceeding the {:?} slow-poll threshold",
+                        elapsed, slow_poll_threshold
+                    );
+                }
 
-        for key in pods_being_deleted.keys() {
-            if pods_being_deleted.get(key).unwrap_or(&0) < &now {
-                keys_to_remove.push(key.clone());
+                watched_pods.clear();
+                for pod in pods {
+                    apply_pod(
+                        pod,
+                        config,
+                        &mut watched_pods,
+                        &mut cleanup_tracker,
+                        &cleanup,
+                        metrics,
+                    )
+                    .await;
+                }
             }
         }
 
-        for key in keys_to_remove {
-            pods_being_deleted.remove(&key);
-        }
+        metrics.watched_pods.set(watched_pods.len() as i64);
     }
 }
 
-#[instrument(skip(cleanup))]
-async fn check_all_pods(
-    critical_deadline: i64,
-    pods_being_deleted: &mut BTreeMap<String, i64>,
+/// Updates the in-memory annotated-pod set for a single `Applied` pod and,
+/// if it's newly or still watched, re-evaluates it for cleanup.
+/// Pods that no longer carry the critical-containers annotation drop out of
+/// the watched set instead of being evaluated.
+#[instrument(skip(pod, config, watched_pods, cleanup_tracker, cleanup, metrics))]
+async fn apply_pod(
+    pod: Pod,
+    config: &Config,
+    watched_pods: &mut BTreeMap<String, PodContainer>,
+    cleanup_tracker: &mut retry::CleanupTracker,
     cleanup: &cleanup::CleanupPod,
-) -> Result<(), errors::kubernetes::Error> {
-    info!("Fetching pod statuses");
-    let watched_pods = get_pods_status().await?;
-    for pod_container in watched_pods {
-        let uid = match &pod_container.pod.metadata.uid {
-            Some(uuid) => uuid.to_string(),
-            None => continue,
-        };
+    metrics: &Metrics,
+) {
+    let uid = match &pod.metadata.uid {
+        Some(uid) => uid.clone(),
+        None => return,
+    };
+
+    // Namespace overrides are resolved once per pod, since the annotation
+    // fallback (default kill condition) and cleanup both need them.
+    let namespace_config = config.for_namespace(pod.metadata.namespace.as_deref().unwrap_or(""));
 
-        if pods_being_deleted.contains_key(&uid) {
-            debug!("Ignoring pod as it's being deleted.");
-            continue;
+    let pod_container = match build_pod_container(pod, namespace_config.default_kill_condition.clone()) {
+        Some(pod_container) => pod_container,
+        None => {
+            watched_pods.remove(&uid);
+            return;
         }
+    };
 
-        {
-            let pod_container = pod_container.clone();
-            let critical_containers = &pod_container.critical_containers;
-            let critical_containers = critical_containers.join(",");
-            info!(
-                "Processing pod {critical_containers:?}",
-                critical_containers = &critical_containers
-            );
-        };
+    watched_pods.insert(uid.clone(), pod_container.clone());
+
+    let now = Utc::now().timestamp_millis();
 
-        if pod_container.should_be_terminated(&critical_deadline) {
-            if let Err(e) = cleanup.cleanup_pod(&pod_container.pod).await {
+    if cleanup_tracker.is_suppressed(&uid, now) {
+        debug!("Ignoring pod as it's being deleted or was recently dead-lettered.");
+        return;
+    }
+
+    {
+        let critical_containers = pod_container.critical_containers.join(",");
+        info!(
+            "Processing pod {critical_containers:?}",
+            critical_containers = &critical_containers
+        );
+    };
+
+    let critical_deadline_ms = namespace_config.critical_deadline.as_millis() as i64;
+
+    if pod_container.should_be_terminated(&critical_deadline_ms) {
+        metrics.pods_terminated_total.inc();
+
+        let cleanup_result = cleanup
+            .cleanup_pod(
+                &pod_container.pod,
+                &namespace_config.istio_container_name,
+                namespace_config.istio_deadline,
+            )
+            .await;
+
+        match cleanup_result {
+            Ok(_) => cleanup_tracker.record_success(uid, now),
+            Err(e) => {
                 error!(
-                    "There was an error while trying to delete Pos. Error: {:?}",
+                    "There was an error while trying to delete Pod. Error: {:?}",
                     e
                 );
+                cleanup_tracker.record_failure(uid, now, config.max_cleanup_attempts, metrics);
             }
-
-            pods_being_deleted
-                .entry(uid)
-                .or_insert(Utc::now().timestamp_millis() + 10_000);
         }
     }
+}
 
-    Ok(())
+/// A reason a single critical container counted towards
+/// `critical_containers_dead`, kept around only so it can be logged.
+#[derive(Debug, Clone, PartialEq)]
+enum KillReason {
+    Terminated,
+    Restarted {
+        count: i32,
+        exit_code: i32,
+        reason: String,
+    },
+    Waiting(String),
+    NotReady,
 }
 
 #[derive(Debug, Clone)]
 struct PodContainer {
     pod: Pod,
-    name: String,
-    namespace: String,
     condition: KillCondition,
     critical_containers: Vec<String>,
+    restart_threshold: Option<i32>,
+    waiting_reasons: BTreeSet<String>,
+    not_ready_threshold: Option<i64>,
 }
 
 impl PodContainer {
@@ -194,15 +355,15 @@ impl PodContainer {
     fn should_be_terminated(&self, crit_deadline: &i64) -> bool {
         trace!("Pods current status: {:?}", &self.pod.status);
 
-        let container_statuses = match &self.pod.status {
+        let status = match &self.pod.status {
             None => {
                 warn!("Pod didn't return a status, assuming everything is running");
                 return false;
             }
-            Some(status) => &status.container_statuses,
+            Some(status) => status,
         };
 
-        let container_statuses = match container_statuses {
+        let container_statuses = match &status.container_statuses {
             Some(container_statuses) => container_statuses,
             None => {
                 warn!("Pod didn't return a container_statuses, assuming everything is running");
@@ -210,6 +371,14 @@ impl PodContainer {
             }
         };
 
+        let not_ready_since = status
+            .conditions
+            .as_ref()
+            .and_then(|conditions| conditions.iter().find(|c| c.type_ == "Ready"))
+            .filter(|ready| ready.status == "False")
+            .and_then(|ready| ready.last_transition_time.as_ref())
+            .map(|time| time.0.timestamp_millis());
+
         let mut critical_containers: BTreeSet<String> = BTreeSet::new();
         for container in &self.critical_containers {
             critical_containers.insert(container.clone());
@@ -220,35 +389,22 @@ impl PodContainer {
             trace!("Critical containers: {:?}", &critical_containers);
             trace!("Processing container {}", &status.name);
             if critical_containers.contains(&status.name) {
-                match &status.state {
-                    Some(state) => {
-                        if let Some(terminated) = &state.terminated {
-                            let past_deadline = match &terminated.finished_at {
-                                Some(time) => {
-                                    time.0.timestamp_millis() + crit_deadline
-                                        > Utc::now().timestamp_millis()
-                                }
-                                None => true,
-                            };
-
-                            if past_deadline {
-                                critical_containers_dead += 1;
-                                info!(
-                                    "Critical container {name} has exited, and deadline passed!",
-                                    name = &status.name
-                                );
-                            } else {
-                                info!( "Critical container {name} has exited, but hasn't passed the deadline.", name = &status.name);
-                            }
-                        }
-                    }
-                    None => {
-                        warn!(
-                            "Critical container {name} didn't return a status, assuming it's ok",
-                            name = &status.name
-                        );
-                    }
+                let reasons = self.container_kill_reasons(status, crit_deadline, not_ready_since);
+
+                if reasons.is_empty() {
+                    info!(
+                        "Critical container {name} hasn't matched a kill condition.",
+                        name = &status.name
+                    );
+                } else {
+                    critical_containers_dead += 1;
+                    info!(
+                        "Critical container {name} matched kill condition(s): {reasons:?}",
+                        name = &status.name,
+                        reasons = &reasons
+                    );
                 }
+
                 critical_containers.remove(&status.name);
             }
         }
@@ -258,7 +414,7 @@ impl PodContainer {
                 "Unable to find critical container(s): {}",
                 &critical_containers
                     .iter()
-                    .map(|x| x.clone())
+                    .cloned()
                     .collect::<Vec<String>>()
                     .join(", ")
             );
@@ -269,63 +425,261 @@ impl PodContainer {
             KillCondition::All => critical_containers_dead == self.critical_containers.len(),
         }
     }
-}
 
-#[instrument]
-async fn get_pods_status() -> Result<Vec<PodContainer>, errors::kubernetes::Error> {
-    use kube::api::ListParams;
+    /// Every kill condition a single critical container currently matches.
+    /// A container can match more than one (e.g. it restarted *and* is
+    /// currently waiting in a backoff), all of which get logged.
+    fn container_kill_reasons(
+        &self,
+        status: &k8s_openapi::api::core::v1::ContainerStatus,
+        crit_deadline: &i64,
+        not_ready_since: Option<i64>,
+    ) -> Vec<KillReason> {
+        let mut reasons = Vec::new();
+        let now = Utc::now().timestamp_millis();
 
-    let client = Client::try_default().await?;
-    let pods: Api<Pod> = Api::all(client);
-    let pods = pods.list(&ListParams::default()).await?;
+        if let Some(state) = &status.state {
+            if let Some(terminated) = &state.terminated {
+                let past_deadline = match &terminated.finished_at {
+                    Some(time) => time.0.timestamp_millis() + crit_deadline <= now,
+                    None => true,
+                };
 
-    let watched_pods: Vec<PodContainer> = pods
-        .items
-        .into_iter()
-        .filter(|pod| {
-            if pod.metadata.name.is_none() || pod.metadata.namespace.is_none() {
-                return false;
+                if past_deadline {
+                    reasons.push(KillReason::Terminated);
+                }
             }
-            match &pod.metadata.annotations {
-                Some(annotation) => annotation.contains_key("podwatcher/critical-containers"),
-                None => false,
+
+            if let Some(waiting) = &state.waiting {
+                if let Some(waiting_reason) = &waiting.reason {
+                    if self.waiting_reasons.contains(waiting_reason) {
+                        reasons.push(KillReason::Waiting(waiting_reason.clone()));
+                    }
+                }
             }
-        })
-        .map(|pod| {
-            let name = pod.metadata.name.clone().unwrap();
-            let namespace = pod.metadata.namespace.clone().unwrap();
-            let annotations = pod.metadata.annotations.clone().unwrap();
-            let critial_annotation: &String =
-                annotations.get("podwatcher/critical-containers").unwrap();
-            let critical_containers = critial_annotation
-                .replace(" ", "")
-                .split('.')
-                .map(|x| x.to_string())
-                .collect();
-
-            let default_any = "any".to_string();
-            let condition = annotations
-                .get("podwatcher/condition")
-                .unwrap_or(&default_any);
-
-            let condition = match condition.to_lowercase().as_str() {
-                "any" | "" => KillCondition::Any,
-                "all" => KillCondition::All,
-                e => {
-                    warn!("Unable to parse {}, assuming any", e);
-                    KillCondition::Any
+        }
+
+        if let Some(threshold) = self.restart_threshold {
+            if status.restart_count >= threshold {
+                let (exit_code, last_reason) = status
+                    .last_state
+                    .as_ref()
+                    .and_then(|last_state| last_state.terminated.as_ref())
+                    .map(|terminated| {
+                        (
+                            terminated.exit_code,
+                            terminated.reason.clone().unwrap_or_default(),
+                        )
+                    })
+                    .unwrap_or_default();
+
+                reasons.push(KillReason::Restarted {
+                    count: status.restart_count,
+                    exit_code,
+                    reason: last_reason,
+                });
+            }
+        }
+
+        if !status.ready {
+            if let Some(threshold) = self.not_ready_threshold {
+                if let Some(since) = not_ready_since {
+                    if since + threshold <= now {
+                        reasons.push(KillReason::NotReady);
+                    }
                 }
-            };
-
-            PodContainer {
-                pod,
-                name,
-                namespace,
-                condition,
-                critical_containers,
             }
-        })
+        }
+
+        reasons
+    }
+}
+
+/// Builds a `PodContainer` out of a freshly observed pod, or `None` if the
+/// pod isn't (or is no longer) annotated with `podwatcher/critical-containers`.
+fn build_pod_container(pod: Pod, default_condition: KillCondition) -> Option<PodContainer> {
+    if pod.metadata.name.is_none() || pod.metadata.namespace.is_none() {
+        return None;
+    }
+
+    let annotations = pod.metadata.annotations.clone().unwrap_or_default();
+    let critial_annotation = annotations.get("podwatcher/critical-containers")?;
+    let critical_containers = critial_annotation
+        .replace(" ", "")
+        .split('.')
+        .map(|x| x.to_string())
         .collect();
 
-    Ok(watched_pods)
+    let condition = match annotations.get("podwatcher/condition") {
+        None => default_condition,
+        Some(value) => match value.to_lowercase().as_str() {
+            "any" | "" => KillCondition::Any,
+            "all" => KillCondition::All,
+            e => {
+                warn!("Unable to parse {}, assuming any", e);
+                KillCondition::Any
+            }
+        },
+    };
+
+    let restart_threshold = annotations
+        .get("podwatcher/restart-threshold")
+        .and_then(|value| match value.parse::<i32>() {
+            Ok(threshold) => Some(threshold),
+            Err(e) => {
+                warn!("Unable to parse podwatcher/restart-threshold: {}", e);
+                None
+            }
+        });
+
+    let waiting_reasons = annotations
+        .get("podwatcher/waiting-reasons")
+        .map(|value| {
+            value
+                .split(',')
+                .map(|reason| reason.trim().to_string())
+                .filter(|reason| !reason.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Disabled by default: a pod is never Ready the moment it starts, so
+    // without an explicit opt-in this would reap every pod during a normal
+    // rollout, not just ones that are stuck.
+    let not_ready_threshold = annotations
+        .get("podwatcher/not-ready-threshold")
+        .and_then(|value| match humantime::parse_duration(value) {
+            Ok(duration) => Some(duration.as_millis() as i64),
+            Err(e) => {
+                warn!("Unable to parse podwatcher/not-ready-threshold: {}", e);
+                None
+            }
+        });
+
+    Some(PodContainer {
+        pod,
+        condition,
+        critical_containers,
+        restart_threshold,
+        waiting_reasons,
+        not_ready_threshold,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{ContainerState, ContainerStateTerminated, ContainerStateWaiting};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    fn base_container() -> PodContainer {
+        PodContainer {
+            pod: Pod::default(),
+            condition: KillCondition::Any,
+            critical_containers: vec!["app".into()],
+            restart_threshold: None,
+            waiting_reasons: BTreeSet::new(),
+            not_ready_threshold: None,
+        }
+    }
+
+    fn status_with(state: Option<ContainerState>, restart_count: i32, ready: bool) -> k8s_openapi::api::core::v1::ContainerStatus {
+        k8s_openapi::api::core::v1::ContainerStatus {
+            name: "app".into(),
+            ready,
+            restart_count,
+            state,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn terminated_past_deadline_is_killed() {
+        let container = base_container();
+        let status = status_with(
+            Some(ContainerState {
+                terminated: Some(ContainerStateTerminated {
+                    exit_code: 1,
+                    finished_at: Some(Time(Utc::now() - chrono::Duration::seconds(10))),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            0,
+            false,
+        );
+
+        let reasons = container.container_kill_reasons(&status, &1_000, None);
+        assert_eq!(reasons, vec![KillReason::Terminated]);
+    }
+
+    #[test]
+    fn restart_below_threshold_does_not_match() {
+        let mut container = base_container();
+        container.restart_threshold = Some(3);
+        let status = status_with(None, 2, true);
+        assert!(container.container_kill_reasons(&status, &1_000, None).is_empty());
+    }
+
+    #[test]
+    fn restart_at_threshold_matches() {
+        let mut container = base_container();
+        container.restart_threshold = Some(3);
+        let status = status_with(None, 3, true);
+        let reasons = container.container_kill_reasons(&status, &1_000, None);
+        assert!(matches!(
+            reasons.as_slice(),
+            [KillReason::Restarted { count: 3, .. }]
+        ));
+    }
+
+    #[test]
+    fn not_ready_without_opt_in_is_never_killed() {
+        let container = base_container();
+        let status = status_with(None, 0, false);
+        let not_ready_since = Some(Utc::now().timestamp_millis() - 60_000);
+        assert!(container
+            .container_kill_reasons(&status, &1_000, not_ready_since)
+            .is_empty());
+    }
+
+    #[test]
+    fn not_ready_past_its_own_threshold_is_killed_when_opted_in() {
+        let mut container = base_container();
+        container.not_ready_threshold = Some(5_000);
+        let status = status_with(None, 0, false);
+        let not_ready_since = Some(Utc::now().timestamp_millis() - 10_000);
+        let reasons = container.container_kill_reasons(&status, &1_000, not_ready_since);
+        assert_eq!(reasons, vec![KillReason::NotReady]);
+    }
+
+    #[test]
+    fn not_ready_within_its_own_threshold_is_not_killed() {
+        let mut container = base_container();
+        container.not_ready_threshold = Some(60_000);
+        let status = status_with(None, 0, false);
+        let not_ready_since = Some(Utc::now().timestamp_millis() - 1_000);
+        assert!(container
+            .container_kill_reasons(&status, &1_000, not_ready_since)
+            .is_empty());
+    }
+
+    #[test]
+    fn waiting_reason_match() {
+        let mut container = base_container();
+        container.waiting_reasons.insert("CrashLoopBackOff".into());
+        let status = status_with(
+            Some(ContainerState {
+                waiting: Some(ContainerStateWaiting {
+                    reason: Some("CrashLoopBackOff".into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            0,
+            true,
+        );
+        let reasons = container.container_kill_reasons(&status, &1_000, None);
+        assert_eq!(reasons, vec![KillReason::Waiting("CrashLoopBackOff".into())]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,132 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
+use tracing::{error, info};
+
+/// Process-wide Prometheus metrics for pod-watcher, scraped over `/metrics`.
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    pub watched_pods: IntGauge,
+    pub pods_terminated_total: IntCounter,
+    pub istio_shutdowns_total: IntCounter,
+    pub cleanup_successes_total: IntCounter,
+    pub cleanup_failures_total: IntCounter,
+    pub cleanup_dead_lettered_total: IntCounter,
+    pub list_duration_seconds: Histogram,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let watched_pods = IntGauge::new(
+            "podwatcher_watched_pods",
+            "Pods currently matched by the podwatcher/critical-containers annotation",
+        )
+        .unwrap();
+        let pods_terminated_total = IntCounter::new(
+            "podwatcher_pods_terminated_total",
+            "Pods pod-watcher has decided to tear down",
+        )
+        .unwrap();
+        let istio_shutdowns_total = IntCounter::new(
+            "podwatcher_istio_shutdowns_total",
+            "Calls made to Istio's quitquitquit endpoint",
+        )
+        .unwrap();
+        let cleanup_successes_total = IntCounter::new(
+            "podwatcher_cleanup_successes_total",
+            "Pod cleanups (delete or eviction) that succeeded",
+        )
+        .unwrap();
+        let cleanup_failures_total = IntCounter::new(
+            "podwatcher_cleanup_failures_total",
+            "Pod cleanups (delete or eviction) that failed",
+        )
+        .unwrap();
+        let cleanup_dead_lettered_total = IntCounter::new(
+            "podwatcher_cleanup_dead_lettered_total",
+            "Pods whose cleanup kept failing until it was given up on",
+        )
+        .unwrap();
+        let list_duration_seconds = Histogram::with_opts(HistogramOpts::new(
+            "podwatcher_list_duration_seconds",
+            "Duration of each pod list/relist against the Kubernetes API",
+        ))
+        .unwrap();
+
+        registry.register(Box::new(watched_pods.clone())).unwrap();
+        registry
+            .register(Box::new(pods_terminated_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(istio_shutdowns_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(cleanup_successes_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(cleanup_failures_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(cleanup_dead_lettered_total.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(list_duration_seconds.clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            watched_pods,
+            pods_terminated_total,
+            istio_shutdowns_total,
+            cleanup_successes_total,
+            cleanup_failures_total,
+            cleanup_dead_lettered_total,
+            list_duration_seconds,
+        }
+    }
+
+    fn render(&self) -> Vec<u8> {
+        let encoder = TextEncoder::new();
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("prometheus text encoding never fails");
+        buffer
+    }
+}
+
+/// Serves `/metrics` (Prometheus text format) and `/healthz` on `addr` until
+/// the process exits. Runs as its own tokio task so a stuck or errored watch
+/// loop doesn't take the scrape target down with it.
+pub async fn serve(addr: SocketAddr, metrics: Arc<Metrics>) {
+    let make_svc = make_service_fn(move |_conn| {
+        let metrics = metrics.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(req, metrics.clone()))) }
+    });
+
+    info!("Admin server listening on {}", addr);
+    if let Err(e) = Server::bind(&addr).serve(make_svc).await {
+        error!("Admin server failed: {:?}", e);
+    }
+}
+
+async fn handle(req: Request<Body>, metrics: Arc<Metrics>) -> Result<Response<Body>, Infallible> {
+    let response = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/metrics") => Response::new(Body::from(metrics.render())),
+        (&Method::GET, "/healthz") => Response::new(Body::from("ok")),
+        _ => {
+            let mut response = Response::new(Body::from("not found"));
+            *response.status_mut() = StatusCode::NOT_FOUND;
+            response
+        }
+    };
+
+    Ok(response)
+}
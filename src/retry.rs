@@ -0,0 +1,169 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use tracing::error;
+
+use crate::metrics::Metrics;
+
+const INITIAL_BACKOFF_MS: i64 = 1_000;
+const MAX_BACKOFF_MS: i64 = 60_000;
+const SUCCESS_SUPPRESSION_MS: i64 = 10_000;
+
+#[derive(Debug, Clone)]
+struct Attempt {
+    count: u32,
+    next_attempt_at: i64,
+}
+
+/// Tracks in-flight and failed pod cleanups so a transient failure gets
+/// retried with exponential backoff instead of being silently forgotten,
+/// while a cleanup that keeps failing is eventually dead-lettered instead
+/// of retried forever.
+#[derive(Debug, Default)]
+pub struct CleanupTracker {
+    attempts: BTreeMap<String, Attempt>,
+    dead_letters: BTreeSet<String>,
+}
+
+impl CleanupTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if `uid` is dead-lettered, or its next retry (or its success
+    /// suppression window) hasn't arrived yet.
+    pub fn is_suppressed(&self, uid: &str, now: i64) -> bool {
+        if self.dead_letters.contains(uid) {
+            return true;
+        }
+
+        match self.attempts.get(uid) {
+            Some(attempt) => attempt.next_attempt_at > now,
+            None => false,
+        }
+    }
+
+    pub fn record_success(&mut self, uid: String, now: i64) {
+        self.attempts.insert(
+            uid,
+            Attempt {
+                count: 0,
+                next_attempt_at: now + SUCCESS_SUPPRESSION_MS,
+            },
+        );
+    }
+
+    /// Schedules a retry with capped exponential backoff (1s, 2s, 4s, ...
+    /// up to `MAX_BACKOFF_MS`), or dead-letters `uid` once `max_attempts`
+    /// has been reached.
+    pub fn record_failure(&mut self, uid: String, now: i64, max_attempts: u32, metrics: &Metrics) {
+        let count = self.attempts.get(&uid).map_or(0, |a| a.count) + 1;
+
+        if count >= max_attempts {
+            error!(
+                "Giving up on cleaning up pod {} after {} attempts, dead-lettering it",
+                uid, count
+            );
+            self.attempts.remove(&uid);
+            self.dead_letters.insert(uid);
+            metrics.cleanup_dead_lettered_total.inc();
+            return;
+        }
+
+        // Cap the shift itself, not just the multiplication: `count` comes
+        // from the user-supplied `--max-cleanup-attempts` with no upper
+        // bound, and `1i64 << 64` overflows long before `saturating_mul`
+        // gets a chance to clamp anything. 32 is already far past the point
+        // (2^6) where the multiplication alone exceeds `MAX_BACKOFF_MS`, so
+        // capping here only has to avoid `1i64 << shift` itself overflowing
+        // or setting the sign bit, not pick a "tight" bound.
+        let shift = (count - 1).min(32);
+        let backoff_ms = INITIAL_BACKOFF_MS
+            .saturating_mul(1i64 << shift)
+            .min(MAX_BACKOFF_MS);
+
+        self.attempts.insert(
+            uid,
+            Attempt {
+                count,
+                next_attempt_at: now + backoff_ms,
+            },
+        );
+    }
+
+    /// Forgets everything about `uid`, e.g. once the pod itself is gone.
+    pub fn forget(&mut self, uid: &str) {
+        self.attempts.remove(uid);
+        self.dead_letters.remove(uid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::Metrics;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let mut tracker = CleanupTracker::new();
+        let metrics = Metrics::new();
+
+        tracker.record_failure("pod-a".into(), 0, 100, &metrics);
+        assert_eq!(tracker.attempts.get("pod-a").unwrap().next_attempt_at, 1_000);
+
+        tracker.record_failure("pod-a".into(), 0, 100, &metrics);
+        assert_eq!(tracker.attempts.get("pod-a").unwrap().next_attempt_at, 2_000);
+
+        tracker.record_failure("pod-a".into(), 0, 100, &metrics);
+        assert_eq!(tracker.attempts.get("pod-a").unwrap().next_attempt_at, 4_000);
+    }
+
+    #[test]
+    fn backoff_never_exceeds_max_even_at_huge_attempt_counts() {
+        let mut tracker = CleanupTracker::new();
+        let metrics = Metrics::new();
+
+        for _ in 0..200 {
+            tracker.record_failure("pod-a".into(), 0, u32::MAX, &metrics);
+        }
+
+        let attempt = tracker.attempts.get("pod-a").unwrap();
+        assert!(attempt.next_attempt_at > 0);
+        assert_eq!(attempt.next_attempt_at, MAX_BACKOFF_MS);
+    }
+
+    #[test]
+    fn dead_letters_once_max_attempts_reached() {
+        let mut tracker = CleanupTracker::new();
+        let metrics = Metrics::new();
+
+        for _ in 0..3 {
+            tracker.record_failure("pod-a".into(), 0, 3, &metrics);
+        }
+
+        assert!(tracker.is_suppressed("pod-a", i64::MAX));
+        assert!(!tracker.attempts.contains_key("pod-a"));
+    }
+
+    #[test]
+    fn success_suppresses_until_the_window_passes() {
+        let mut tracker = CleanupTracker::new();
+
+        tracker.record_success("pod-a".into(), 0);
+
+        assert!(tracker.is_suppressed("pod-a", SUCCESS_SUPPRESSION_MS - 1));
+        assert!(!tracker.is_suppressed("pod-a", SUCCESS_SUPPRESSION_MS));
+    }
+
+    #[test]
+    fn forget_clears_both_attempts_and_dead_letters() {
+        let mut tracker = CleanupTracker::new();
+        let metrics = Metrics::new();
+
+        for _ in 0..3 {
+            tracker.record_failure("pod-a".into(), 0, 3, &metrics);
+        }
+        tracker.forget("pod-a");
+
+        assert!(!tracker.is_suppressed("pod-a", 0));
+    }
+}